@@ -0,0 +1,525 @@
+#[cfg(desktop)]
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(desktop)]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+#[cfg(desktop)]
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_shell::process::CommandChild;
+#[cfg(desktop)]
+use tauri_plugin_shell::process::CommandEvent;
+#[cfg(desktop)]
+use tauri_plugin_shell::ShellExt;
+#[cfg(desktop)]
+use tokio::sync::Notify;
+
+/// Shortest delay between restart attempts.
+#[cfg(desktop)]
+const BACKOFF_MIN: Duration = Duration::from_millis(500);
+/// Upper bound on the restart delay so a persistently failing backend still
+/// gets retried every so often.
+#[cfg(desktop)]
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// How long the sidecar has to stay alive before we consider the restart
+/// "successful" and reset the backoff counter.
+#[cfg(desktop)]
+const STABLE_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Path polled to decide whether the backend is accepting requests.
+const HEALTH_PATH: &str = "/health";
+/// Delay between readiness polls.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Overall budget for the backend to come up before we give up on it.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Lifecycle phase of the `brain` sidecar, mirrored to the frontend through the
+/// `brain://status` event so the UI can reflect backend health.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BrainPhase {
+  Starting,
+  Running,
+  Crashed,
+  Restarting,
+  Stopped,
+}
+
+/// Host/port the sidecar is listening on. Allocated once per launch and kept
+/// stable across restarts so the frontend's endpoint never changes underneath
+/// it.
+#[derive(Clone, Debug, Serialize)]
+pub struct Endpoint {
+  pub host: String,
+  pub port: u16,
+}
+
+impl Endpoint {
+  /// The `http://host:port` base URL the frontend should talk to.
+  pub fn url(&self) -> String {
+    format!("http://{}:{}", self.host, self.port)
+  }
+}
+
+/// Tauri-managed handle to the supervised sidecar. Holds the live
+/// [`CommandChild`] so the supervisor can be stopped cleanly, the last phase we
+/// emitted, and the endpoint the backend was bound to.
+#[derive(Default)]
+pub struct BrainState {
+  child: Mutex<Option<CommandChild>>,
+  phase: Mutex<Option<BrainPhase>>,
+  endpoint: Mutex<Option<Endpoint>>,
+  /// Set when the user has explicitly stopped the backend so the supervisor
+  /// stops restarting it.
+  stopped: AtomicBool,
+  /// Whether a supervisor task is currently running, to avoid launching two.
+  #[cfg(desktop)]
+  running: AtomicBool,
+  /// Signalled by `restart_brain` to cut a mid-backoff supervisor's sleep short
+  /// so a restart takes effect immediately.
+  #[cfg(desktop)]
+  restart: Arc<Notify>,
+}
+
+impl BrainState {
+  #[cfg(desktop)]
+  fn set_child(&self, child: CommandChild) {
+    *self.child.lock().unwrap() = Some(child);
+  }
+
+  fn take_child(&self) -> Option<CommandChild> {
+    self.child.lock().unwrap().take()
+  }
+
+  fn set_phase(&self, phase: BrainPhase) {
+    *self.phase.lock().unwrap() = Some(phase);
+  }
+
+  /// The last phase emitted over `brain://status`, if any.
+  pub fn phase(&self) -> Option<BrainPhase> {
+    *self.phase.lock().unwrap()
+  }
+
+  fn set_endpoint(&self, endpoint: Endpoint) {
+    *self.endpoint.lock().unwrap() = Some(endpoint);
+  }
+
+  /// The endpoint the sidecar was bound to, if it has been allocated yet.
+  pub fn endpoint(&self) -> Option<Endpoint> {
+    self.endpoint.lock().unwrap().clone()
+  }
+
+  fn set_stopped(&self, stopped: bool) {
+    self.stopped.store(stopped, Ordering::SeqCst);
+  }
+
+  #[cfg(desktop)]
+  fn is_stopped(&self) -> bool {
+    self.stopped.load(Ordering::SeqCst)
+  }
+
+  /// Atomically claim the supervisor slot, returning `true` only for the caller
+  /// that flipped it from idle to running. Two concurrent commands can't both
+  /// win, so at most one `supervise` task is ever spawned.
+  #[cfg(desktop)]
+  fn try_claim_running(&self) -> bool {
+    self
+      .running
+      .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+      .is_ok()
+  }
+
+  /// Release the supervisor slot when the task exits.
+  #[cfg(desktop)]
+  fn clear_running(&self) {
+    self.running.store(false, Ordering::SeqCst);
+  }
+
+  /// Wake a supervisor that's sleeping out its restart backoff. The permit is
+  /// sticky, so a restart requested while the supervisor is between sleeps is
+  /// still observed on its next wait.
+  #[cfg(desktop)]
+  fn request_restart(&self) {
+    self.restart.notify_one();
+  }
+
+  /// A clone of the restart signal the supervisor can await.
+  #[cfg(desktop)]
+  fn restart_signal(&self) -> Arc<Notify> {
+    self.restart.clone()
+  }
+}
+
+/// Snapshot of the backend's health reported to the frontend by
+/// [`brain_status`].
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrainStatus {
+  pub phase: BrainPhase,
+  pub port: Option<u16>,
+  pub url: Option<String>,
+}
+
+/// Return the `http://host:port` base URL the frontend should use to reach the
+/// backend, or an empty string if the endpoint has not been allocated yet.
+#[tauri::command]
+pub fn brain_endpoint(state: State<'_, BrainState>) -> String {
+  state.endpoint().map(|e| e.url()).unwrap_or_default()
+}
+
+/// Report the current backend phase and endpoint.
+#[tauri::command]
+pub fn brain_status(state: State<'_, BrainState>) -> BrainStatus {
+  let endpoint = state.endpoint();
+  BrainStatus {
+    phase: state.phase().unwrap_or(BrainPhase::Starting),
+    port: endpoint.as_ref().map(|e| e.port),
+    url: endpoint.map(|e| e.url()),
+  }
+}
+
+/// Restart the backend, returning the endpoint it will come back up on. Killing
+/// a live child ends the supervisor's watch loop; signalling the restart wakes
+/// a supervisor that's mid-backoff so the respawn is immediate regardless of
+/// its state. If the backend had been stopped, the supervisor is brought back.
+#[tauri::command]
+pub fn restart_brain(app: AppHandle) -> String {
+  let state = app.state::<BrainState>();
+  set_phase(&app, BrainPhase::Restarting);
+  if let Some(child) = state.take_child() {
+    if let Err(err) = child.kill() {
+      log::warn!("failed to kill brain sidecar during restart: {err}");
+    }
+  }
+  #[cfg(desktop)]
+  state.request_restart();
+  launch_supervisor(app.clone());
+  app
+    .state::<BrainState>()
+    .endpoint()
+    .map(|e| e.url())
+    .unwrap_or_default()
+}
+
+/// Start (or resume) the backend supervisor.
+#[tauri::command]
+pub fn start_brain(app: AppHandle) {
+  launch_supervisor(app);
+}
+
+/// Stop the backend and keep it stopped until [`start_brain`] or
+/// [`restart_brain`] is called.
+#[tauri::command]
+pub fn stop_brain(app: AppHandle) -> Result<(), String> {
+  let state = app.state::<BrainState>();
+  state.set_stopped(true);
+  if let Some(child) = state.take_child() {
+    child.kill().map_err(|err| err.to_string())?;
+  }
+  Ok(())
+}
+
+/// Bring up the backend. On desktop this supervises a spawned `brain` sidecar;
+/// on mobile — where shell sidecars can't launch external binaries — it binds
+/// managed state to a configured endpoint instead. Either way the rest of the
+/// app sees the same managed `Endpoint`, events, and commands.
+pub fn start(app: AppHandle) -> tauri::Result<()> {
+  #[cfg(desktop)]
+  return start_desktop(app);
+  #[cfg(mobile)]
+  return start_mobile(app);
+}
+
+/// Desktop bootstrap: allocate a free port, record the endpoint, create the
+/// main window with the endpoint injected, and launch the supervision task.
+#[cfg(desktop)]
+fn start_desktop(app: AppHandle) -> tauri::Result<()> {
+  let endpoint = match alloc_endpoint() {
+    Ok(endpoint) => endpoint,
+    Err(err) => {
+      log::error!("failed to allocate a port for the brain sidecar: {err}");
+      return Ok(());
+    }
+  };
+
+  log::info!("brain endpoint allocated at {}", endpoint.url());
+  app.state::<BrainState>().set_endpoint(endpoint.clone());
+  inject_endpoint(&app, &endpoint)?;
+
+  launch_supervisor(app);
+  Ok(())
+}
+
+/// Mobile bootstrap: there's no sidecar to spawn, so point the app at the
+/// backend endpoint configured for this build (overridable via the
+/// `BRAIN_ENDPOINT` env var) and mark it running. The readiness probe still
+/// gates the window against that endpoint.
+#[cfg(mobile)]
+fn start_mobile(app: AppHandle) -> tauri::Result<()> {
+  let endpoint = mobile_endpoint();
+  log::info!("brain backend configured at {} (mobile)", endpoint.url());
+  app.state::<BrainState>().set_endpoint(endpoint.clone());
+  inject_endpoint(&app, &endpoint)?;
+  set_phase(&app, BrainPhase::Running);
+  Ok(())
+}
+
+/// Resolve the backend endpoint for mobile builds from `BRAIN_ENDPOINT`
+/// (`host:port`), falling back to the loopback default.
+#[cfg(mobile)]
+fn mobile_endpoint() -> Endpoint {
+  if let Ok(raw) = std::env::var("BRAIN_ENDPOINT") {
+    if let Some((host, port)) = raw.rsplit_once(':') {
+      if let Ok(port) = port.parse::<u16>() {
+        return Endpoint {
+          host: host.to_string(),
+          port,
+        };
+      }
+    }
+    log::warn!("ignoring malformed BRAIN_ENDPOINT {raw:?}");
+  }
+  Endpoint {
+    host: "127.0.0.1".to_string(),
+    port: 8000,
+  }
+}
+
+/// Spawn the supervision task if one isn't already running. Clears the stopped
+/// flag so a previously stopped backend comes back up.
+#[cfg(desktop)]
+fn launch_supervisor(app: AppHandle) {
+  let state = app.state::<BrainState>();
+  state.set_stopped(false);
+  let Some(endpoint) = state.endpoint() else {
+    log::error!("cannot start supervisor: no brain endpoint allocated");
+    return;
+  };
+  // Claim the supervisor slot atomically; bail if another task already holds it.
+  if !state.try_claim_running() {
+    return;
+  }
+  tauri::async_runtime::spawn(supervise(app.clone(), endpoint));
+}
+
+/// Mobile builds have no sidecar to supervise; the endpoint is fixed at
+/// startup, so this is a no-op.
+#[cfg(mobile)]
+fn launch_supervisor(_app: AppHandle) {}
+
+/// Bind to port 0 so the OS hands us a free port, read it back, then release
+/// the listener so the sidecar can bind it itself.
+#[cfg(desktop)]
+fn alloc_endpoint() -> std::io::Result<Endpoint> {
+  let listener = TcpListener::bind("127.0.0.1:0")?;
+  let port = listener.local_addr()?.port();
+  drop(listener);
+  Ok(Endpoint {
+    host: "127.0.0.1".to_string(),
+    port,
+  })
+}
+
+/// Create the main window with the endpoint baked into an initialization
+/// script, so `window.__BRAIN_ENDPOINT__` is defined before the frontend's
+/// document loads and survives navigations — unlike a one-shot post-setup
+/// `eval`, which races the asynchronous page load and can be lost. The window
+/// starts hidden; [`gate_window`] reveals it once the backend is ready.
+///
+/// `tauri.conf.json` declares no windows (`"windows": []`), so this is the only
+/// window labelled `main` and the build can't collide. A build failure is
+/// propagated rather than swallowed — without this window the frontend never
+/// learns its endpoint, so the app must fail loudly instead.
+fn inject_endpoint(app: &AppHandle, endpoint: &Endpoint) -> tauri::Result<()> {
+  let script = format!("window.__BRAIN_ENDPOINT__ = {:?};", endpoint.url());
+  WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+    .initialization_script(&script)
+    .visible(false)
+    .build()?;
+  Ok(())
+}
+
+/// Reveal the main window only once the backend answers its health check. The
+/// window is created hidden (`visible(false)` in [`inject_endpoint`]), so there
+/// is no visible flash to hide after the fact — we simply show it once ready.
+/// On timeout we surface an error dialog and tear the app down rather than
+/// leaving the user staring at a window whose backend never came up.
+pub fn gate_window(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let Some(endpoint) = app.state::<BrainState>().endpoint() else {
+      log::error!("no brain endpoint available; cannot probe for readiness");
+      return;
+    };
+
+    if wait_for_ready(&endpoint).await {
+      if let Some(window) = app.get_webview_window("main") {
+        if let Err(err) = window.show() {
+          log::error!("failed to show main window after backend readiness: {err}");
+        }
+      }
+      if let Err(err) = app.emit("brain://ready", &endpoint) {
+        log::error!("failed to emit brain://ready: {err}");
+      }
+    } else {
+      log::error!("brain backend did not become ready within {READY_TIMEOUT:?}");
+      app
+        .dialog()
+        .message("The backend failed to start. The application will now close.")
+        .kind(MessageDialogKind::Error)
+        .title("Backend unavailable")
+        .blocking_show();
+      app.exit(1);
+    }
+  });
+}
+
+/// Poll `GET {endpoint}/health` until it returns success or the readiness
+/// timeout elapses.
+async fn wait_for_ready(endpoint: &Endpoint) -> bool {
+  let url = format!("{}{HEALTH_PATH}", endpoint.url());
+  let client = reqwest::Client::new();
+  let start = Instant::now();
+
+  while start.elapsed() < READY_TIMEOUT {
+    if let Ok(resp) = client.get(&url).send().await {
+      if resp.status().is_success() {
+        return true;
+      }
+    }
+    tokio::time::sleep(READY_POLL_INTERVAL).await;
+  }
+
+  false
+}
+
+#[cfg(desktop)]
+fn spawn_brain(
+  app: &AppHandle,
+  endpoint: &Endpoint,
+) -> tauri_plugin_shell::Result<(Receiver<CommandEvent>, CommandChild)> {
+  app
+    .shell()
+    .sidecar("brain")?
+    .args(["--host", &endpoint.host, "--port", &endpoint.port.to_string()])
+    .spawn()
+}
+
+#[cfg(desktop)]
+async fn supervise(app: AppHandle, endpoint: Endpoint) {
+  let restart = app.state::<BrainState>().restart_signal();
+  let mut backoff = BACKOFF_MIN;
+  loop {
+    // A stop requested while the supervisor was waiting out its backoff ends
+    // the loop here, before anything is respawned.
+    if app.state::<BrainState>().is_stopped() {
+      set_phase(&app, BrainPhase::Stopped);
+      break;
+    }
+
+    set_phase(&app, BrainPhase::Starting);
+
+    let (mut rx, child) = match spawn_brain(&app, &endpoint) {
+      Ok(pair) => pair,
+      Err(err) => {
+        log::error!("failed to spawn brain sidecar: {err}");
+        set_phase(&app, BrainPhase::Crashed);
+        tokio::select! {
+          _ = tokio::time::sleep(backoff) => {
+            backoff = next_backoff(backoff);
+          }
+          _ = restart.notified() => {
+            backoff = BACKOFF_MIN;
+          }
+        }
+        continue;
+      }
+    };
+
+    app.state::<BrainState>().set_child(child);
+    set_phase(&app, BrainPhase::Running);
+    let started = Instant::now();
+
+    while let Some(event) = rx.recv().await {
+      match event {
+        CommandEvent::Stdout(line) => {
+          log::info!(target: "brain", "{}", String::from_utf8_lossy(&line).trim_end());
+        }
+        CommandEvent::Stderr(line) => {
+          log::warn!(target: "brain", "{}", String::from_utf8_lossy(&line).trim_end());
+        }
+        CommandEvent::Error(err) => {
+          log::error!(target: "brain", "{err}");
+        }
+        CommandEvent::Terminated(payload) => {
+          log::warn!("brain sidecar terminated: {payload:?}");
+          break;
+        }
+        _ => {}
+      }
+    }
+
+    app.state::<BrainState>().take_child();
+
+    // A deliberate stop ends the loop; the supervisor is relaunched explicitly
+    // via `launch_supervisor`. A restart only kills the child, so it falls
+    // through to the backoff wait below, which the restart signal cuts short.
+    if app.state::<BrainState>().is_stopped() {
+      set_phase(&app, BrainPhase::Stopped);
+      break;
+    }
+
+    // A process that stayed up long enough is treated as healthy, so the next
+    // crash starts the backoff over from the minimum.
+    if started.elapsed() >= STABLE_THRESHOLD {
+      backoff = BACKOFF_MIN;
+    }
+
+    set_phase(&app, BrainPhase::Crashed);
+    set_phase(&app, BrainPhase::Restarting);
+
+    // Wait out the backoff, but let an explicit restart cut the sleep short and
+    // reset the delay so the user's "Restart backend" takes effect at once.
+    tokio::select! {
+      _ = tokio::time::sleep(backoff) => {
+        backoff = next_backoff(backoff);
+      }
+      _ = restart.notified() => {
+        backoff = BACKOFF_MIN;
+      }
+    }
+  }
+
+  app.state::<BrainState>().clear_running();
+}
+
+/// Kill the sidecar on application exit so it can't outlive the app and keep
+/// holding its port. The backend is marked stopped first so the supervisor
+/// doesn't race to respawn it.
+pub fn shutdown(app: &AppHandle) {
+  let state = app.state::<BrainState>();
+  state.set_stopped(true);
+  if let Some(child) = state.take_child() {
+    if let Err(err) = child.kill() {
+      log::warn!("failed to kill brain sidecar on shutdown: {err}");
+    } else {
+      log::info!("brain sidecar terminated on exit");
+    }
+  }
+}
+
+fn set_phase(app: &AppHandle, phase: BrainPhase) {
+  app.state::<BrainState>().set_phase(phase);
+  if let Err(err) = app.emit("brain://status", phase) {
+    log::error!("failed to emit brain://status: {err}");
+  }
+}
+
+#[cfg(desktop)]
+fn next_backoff(current: Duration) -> Duration {
+  (current * 2).min(BACKOFF_MAX)
+}
@@ -1,9 +1,18 @@
-use tauri_plugin_shell::ShellExt;
+mod brain;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_shell::init())
+    .plugin(tauri_plugin_dialog::init())
+    .manage(brain::BrainState::default())
+    .invoke_handler(tauri::generate_handler![
+      brain::brain_endpoint,
+      brain::brain_status,
+      brain::restart_brain,
+      brain::start_brain,
+      brain::stop_brain,
+    ])
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -13,15 +22,18 @@ pub fn run() {
         )?;
       }
 
-      let _child = app.shell().sidecar("brain")?
-        .args(&["--host", "127.0.0.1", "--port", "8000"])
-        .spawn()
-        .expect("Failed to spawn sidecar process");
+      brain::start(app.handle().clone())?;
+      brain::gate_window(app.handle().clone());
 
-      log::info!("Sidecar process started");
+      log::info!("Sidecar supervisor started");
 
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
-}
\ No newline at end of file
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app_handle, event| {
+      if let tauri::RunEvent::Exit = event {
+        brain::shutdown(app_handle);
+      }
+    });
+}